@@ -0,0 +1,493 @@
+use std::fmt::{self, Debug};
+
+use super::plumbing::*;
+use super::*;
+
+/// `FoldWindows` is an iterator that slides a fixed-width, overlapping window over an underlying
+/// iterator and applies a function over each window, producing a single folded value per window.
+///
+/// Unlike [`FoldChunks`], which partitions the iterator into disjoint groups, the windows
+/// produced here may overlap: window `i` covers elements `[i * step, i * step + window_size)`.
+/// `window_size` must be greater than or equal to `step`, and `step` must be at least 1. The
+/// final window may be shorter than `window_size` if `len` isn't an exact fit, mirroring the
+/// uneven-chunk handling of [`FoldChunks`].
+///
+/// Because adjacent windows can share elements, this adapter clones every shared element into
+/// each window that contains it, so `I::Item` must implement `Clone`.
+///
+/// This struct is created by the [`fold_windows()`] method on [`IndexedParallelIterator`]
+///
+/// [`FoldChunks`]: struct.FoldChunks.html
+/// [`fold_windows()`]: trait.IndexedParallelIterator.html#method.fold_windows
+/// [`IndexedParallelIterator`]: trait.IndexedParallelIterator.html
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+#[derive(Clone)]
+pub struct FoldWindows<I, ID, F>
+where
+    I: IndexedParallelIterator,
+{
+    base: I,
+    window_size: usize,
+    step: usize,
+    identity: ID,
+    fold_op: F,
+}
+
+impl<I: IndexedParallelIterator + Debug, ID, F> Debug for FoldWindows<I, ID, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FoldWindows")
+            .field("base", &self.base)
+            .field("window_size", &self.window_size)
+            .field("step", &self.step)
+            .finish()
+    }
+}
+
+/// The number of windows of width `window_size`, advancing by `step`, that fit over `len`
+/// elements, where the final window is allowed to be shorter than `window_size`.
+fn num_windows(len: usize, window_size: usize, step: usize) -> usize {
+    if len == 0 {
+        0
+    } else if len <= window_size {
+        1
+    } else {
+        1 + crate::math::div_round_up(len - window_size, step)
+    }
+}
+
+impl<I, ID, U, F> FoldWindows<I, ID, F>
+where
+    I: IndexedParallelIterator,
+    I::Item: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, I::Item) -> U + Send + Sync,
+    U: Send,
+{
+    /// Creates a new `FoldWindows` iterator
+    pub(super) fn new(base: I, window_size: usize, step: usize, identity: ID, fold_op: F) -> Self {
+        assert!(step >= 1, "step must not be zero");
+        assert!(window_size >= step, "window_size must be at least step");
+        FoldWindows {
+            base,
+            window_size,
+            step,
+            identity,
+            fold_op,
+        }
+    }
+}
+
+impl<I, ID, U, F> ParallelIterator for FoldWindows<I, ID, F>
+where
+    I: IndexedParallelIterator,
+    I::Item: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, I::Item) -> U + Send + Sync,
+    U: Send,
+{
+    type Item = U;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<U>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<I, ID, U, F> IndexedParallelIterator for FoldWindows<I, ID, F>
+where
+    I: IndexedParallelIterator,
+    I::Item: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, I::Item) -> U + Send + Sync,
+    U: Send,
+{
+    fn len(&self) -> usize {
+        num_windows(self.base.len(), self.window_size, self.step)
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        let len = self.base.len();
+        let num_windows = num_windows(len, self.window_size, self.step);
+        return self.base.with_producer(Callback {
+            len,
+            window_size: self.window_size,
+            step: self.step,
+            num_windows,
+            identity: self.identity,
+            fold_op: self.fold_op,
+            callback,
+        });
+
+        struct Callback<CB, ID, F> {
+            len: usize,
+            window_size: usize,
+            step: usize,
+            num_windows: usize,
+            identity: ID,
+            fold_op: F,
+            callback: CB,
+        }
+
+        impl<T, CB, ID, U, F> ProducerCallback<T> for Callback<CB, ID, F>
+        where
+            CB: ProducerCallback<U>,
+            T: Clone + Send,
+            ID: Fn() -> U + Send + Sync,
+            F: Fn(U, T) -> U + Send + Sync,
+        {
+            type Output = CB::Output;
+
+            fn callback<P>(self, base: P) -> CB::Output
+            where
+                P: Producer<Item = T>,
+            {
+                self.callback.callback(FoldWindowsProducer {
+                    prefix: Vec::new(),
+                    base: Some(base),
+                    base_len: self.len,
+                    suffix: Vec::new(),
+                    num_windows: self.num_windows,
+                    window_size: self.window_size,
+                    step: self.step,
+                    identity: &self.identity,
+                    fold_op: &self.fold_op,
+                })
+            }
+        }
+    }
+}
+
+/// A contiguous run of elements kept around as plain `Vec`s (`prefix`/`suffix`) bracketing a
+/// still-unconsumed slice of the original producer (`base`). Windows that straddle a `split_at`
+/// boundary are satisfied by cloning the small (at most `window_size - step` long) overlap into
+/// both halves, rather than materializing the whole producer up front.
+type Node<T, P> = (Vec<T>, Option<P>, usize, Vec<T>);
+
+fn node_len<T, P>(node: &Node<T, P>) -> usize {
+    node.0.len() + node.2 + node.3.len()
+}
+
+/// Splits a `Node` at absolute position `cut`, dividing ownership of its elements without
+/// cloning anything.
+fn split_node<P: Producer>(node: Node<P::Item, P>, cut: usize) -> (Node<P::Item, P>, Node<P::Item, P>) {
+    let (mut prefix, base, base_len, suffix) = node;
+    if cut <= prefix.len() {
+        let right_prefix = prefix.split_off(cut);
+        ((prefix, None, 0, Vec::new()), (right_prefix, base, base_len, suffix))
+    } else if cut <= prefix.len() + base_len {
+        let base_cut = cut - prefix.len();
+        let (base_left, base_right) = base
+            .expect("base_len > 0 implies base is present")
+            .split_at(base_cut);
+        (
+            (prefix, Some(base_left), base_cut, Vec::new()),
+            (Vec::new(), Some(base_right), base_len - base_cut, suffix),
+        )
+    } else {
+        let mut suffix = suffix;
+        let right_suffix = suffix.split_off(cut - prefix.len() - base_len);
+        (
+            (prefix, base, base_len, suffix),
+            (Vec::new(), None, 0, right_suffix),
+        )
+    }
+}
+
+fn materialize<P: Producer>(node: Node<P::Item, P>) -> Vec<P::Item> {
+    let (prefix, base, _base_len, suffix) = node;
+    let mut elems = prefix;
+    if let Some(base) = base {
+        elems.extend(base.into_iter());
+    }
+    elems.extend(suffix);
+    elems
+}
+
+struct FoldWindowsProducer<'f, P, ID, F>
+where
+    P: Producer,
+{
+    prefix: Vec<P::Item>,
+    base: Option<P>,
+    base_len: usize,
+    suffix: Vec<P::Item>,
+    num_windows: usize,
+    window_size: usize,
+    step: usize,
+    identity: &'f ID,
+    fold_op: &'f F,
+}
+
+impl<'f, P, ID, U, F> Producer for FoldWindowsProducer<'f, P, ID, F>
+where
+    P: Producer,
+    P::Item: Clone + Send,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, P::Item) -> U + Send + Sync,
+{
+    type Item = U;
+    type IntoIter = FoldWindowsSeq<'f, P::Item, ID, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let elems = materialize((self.prefix, self.base, self.base_len, self.suffix));
+        FoldWindowsSeq {
+            elems,
+            window_size: self.window_size,
+            step: self.step,
+            front: 0,
+            back: self.num_windows,
+            identity: self.identity,
+            fold_op: self.fold_op,
+        }
+    }
+
+    fn min_len(&self) -> usize {
+        1
+    }
+
+    fn max_len(&self) -> usize {
+        self.num_windows.max(1)
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let node = (self.prefix, self.base, self.base_len, self.suffix);
+        let local_len = node_len(&node);
+
+        let left_needed = if index == 0 {
+            0
+        } else {
+            ((index - 1) * self.step + self.window_size).min(local_len)
+        };
+        let right_start = (index * self.step).min(local_len);
+        let overlap_len = left_needed.saturating_sub(right_start);
+
+        let (left_node, right_rest) = split_node(node, left_needed);
+        let (left_main, shared_node) = split_node(left_node, left_needed - overlap_len);
+        let shared = materialize(shared_node);
+
+        let (l_prefix, l_base, l_base_len, mut l_suffix) = left_main;
+        l_suffix.extend(shared.iter().cloned());
+
+        let (r_prefix0, r_base, r_base_len, r_suffix) = right_rest;
+        let mut r_prefix = shared;
+        r_prefix.extend(r_prefix0);
+
+        (
+            FoldWindowsProducer {
+                prefix: l_prefix,
+                base: l_base,
+                base_len: l_base_len,
+                suffix: l_suffix,
+                num_windows: index,
+                window_size: self.window_size,
+                step: self.step,
+                identity: self.identity,
+                fold_op: self.fold_op,
+            },
+            FoldWindowsProducer {
+                prefix: r_prefix,
+                base: r_base,
+                base_len: r_base_len,
+                suffix: r_suffix,
+                num_windows: self.num_windows - index,
+                window_size: self.window_size,
+                step: self.step,
+                identity: self.identity,
+                fold_op: self.fold_op,
+            },
+        )
+    }
+}
+
+struct FoldWindowsSeq<'f, T, ID, F> {
+    elems: Vec<T>,
+    window_size: usize,
+    step: usize,
+    front: usize,
+    back: usize,
+    identity: &'f ID,
+    fold_op: &'f F,
+}
+
+impl<'f, T, ID, U, F> FoldWindowsSeq<'f, T, ID, F>
+where
+    T: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, T) -> U + Send + Sync,
+{
+    fn fold_window(&self, window_index: usize) -> U {
+        let start = window_index * self.step;
+        let stop = (start + self.window_size).min(self.elems.len());
+        self.elems[start..stop]
+            .iter()
+            .cloned()
+            .fold((self.identity)(), self.fold_op)
+    }
+}
+
+impl<'f, T, ID, U, F> Iterator for FoldWindowsSeq<'f, T, ID, F>
+where
+    T: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, T) -> U + Send + Sync,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let window = self.fold_window(self.front);
+        self.front += 1;
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'f, T, ID, U, F> ExactSizeIterator for FoldWindowsSeq<'f, T, ID, F>
+where
+    T: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, T) -> U + Send + Sync,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+impl<'f, T, ID, U, F> DoubleEndedIterator for FoldWindowsSeq<'f, T, ID, F>
+where
+    T: Clone,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, T) -> U + Send + Sync,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.fold_window(self.back))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ops::Add;
+
+    fn id() -> i32 {
+        0
+    }
+
+    fn sum<T, U>(x: T, y: U) -> T
+    where
+        T: Add<U, Output = T>,
+    {
+        x + y
+    }
+
+    #[test]
+    #[should_panic(expected = "step must not be zero")]
+    fn check_fold_windows_zero_step() {
+        let _: Vec<i32> = vec![1, 2, 3]
+            .into_par_iter()
+            .fold_windows(2, 0, id, sum)
+            .collect();
+    }
+
+    #[test]
+    #[should_panic(expected = "window_size must be at least step")]
+    fn check_fold_windows_window_smaller_than_step() {
+        let _: Vec<i32> = vec![1, 2, 3]
+            .into_par_iter()
+            .fold_windows(2, 3, id, sum)
+            .collect();
+    }
+
+    #[test]
+    fn check_fold_windows_overlapping() {
+        assert_eq!(
+            vec![0 + 1 + 2, 1 + 2 + 3, 2 + 3 + 4],
+            (0..5)
+                .into_par_iter()
+                .fold_windows(3, 1, id, sum)
+                .collect::<Vec<i32>>()
+        );
+    }
+
+    #[test]
+    fn check_fold_windows_strided() {
+        assert_eq!(
+            vec![0 + 1 + 2, 2 + 3 + 4],
+            (0..5)
+                .into_par_iter()
+                .fold_windows(3, 2, id, sum)
+                .collect::<Vec<i32>>()
+        );
+    }
+
+    #[test]
+    fn check_fold_windows_chunk_like() {
+        assert_eq!(
+            (0..9)
+                .into_par_iter()
+                .fold_chunks(3, id, sum)
+                .collect::<Vec<i32>>(),
+            (0..9)
+                .into_par_iter()
+                .fold_windows(3, 3, id, sum)
+                .collect::<Vec<i32>>()
+        );
+    }
+
+    #[test]
+    fn check_fold_windows_empty() {
+        let v: Vec<i32> = vec![];
+        let expected: Vec<i32> = vec![];
+        assert_eq!(
+            expected,
+            v.into_par_iter()
+                .fold_windows(2, 1, id, sum)
+                .collect::<Vec<i32>>()
+        );
+    }
+
+    #[test]
+    fn check_fold_windows_len() {
+        assert_eq!(4, (0..5).into_par_iter().fold_windows(2, 1, id, sum).len());
+        assert_eq!(3, (0..9).into_par_iter().fold_windows(3, 3, id, sum).len());
+        assert_eq!(0, (0..0).into_par_iter().fold_windows(3, 1, id, sum).len());
+    }
+
+    #[test]
+    fn check_fold_windows_rev() {
+        let mut res: Vec<i32> = vec![];
+        (0..5)
+            .into_par_iter()
+            .fold_windows(3, 1, id, sum)
+            .rev()
+            .collect_into_vec(&mut res);
+        assert_eq!(vec![2 + 3 + 4, 1 + 2 + 3, 0 + 1 + 2], res);
+    }
+}
@@ -0,0 +1,305 @@
+use std::cmp::min;
+use std::fmt::{self, Debug};
+
+use super::plumbing::*;
+use super::*;
+use crate::math::div_round_up;
+
+/// `MapChunks` is an iterator that groups elements of an underlying iterator into chunks and
+/// applies a function over the whole chunk at once, producing a single value for each group.
+///
+/// Unlike [`FoldChunks`], which threads an accumulator through the chunk one item at a time,
+/// `MapChunks` hands the entire chunk to `map_op` as a `Vec`, so it can express reductions that
+/// aren't expressible as an incremental fold, such as sorting a chunk or computing its median.
+///
+/// This struct is created by the [`map_chunks()`] method on [`IndexedParallelIterator`]
+///
+/// [`FoldChunks`]: struct.FoldChunks.html
+/// [`map_chunks()`]: trait.IndexedParallelIterator.html#method.map_chunks
+/// [`IndexedParallelIterator`]: trait.IndexedParallelIterator.html
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+#[derive(Clone)]
+pub struct MapChunks<I, F>
+where
+    I: IndexedParallelIterator,
+{
+    base: I,
+    chunk_size: usize,
+    map_op: F,
+}
+
+impl<I: IndexedParallelIterator + Debug, F> Debug for MapChunks<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapChunks")
+            .field("base", &self.base)
+            .field("chunk_size", &self.chunk_size)
+            .finish()
+    }
+}
+
+impl<I, F, R> MapChunks<I, F>
+where
+    I: IndexedParallelIterator,
+    F: Fn(Vec<I::Item>) -> R + Send + Sync,
+    R: Send,
+{
+    /// Creates a new `MapChunks` iterator
+    pub(super) fn new(base: I, chunk_size: usize, map_op: F) -> Self {
+        MapChunks {
+            base,
+            chunk_size,
+            map_op,
+        }
+    }
+}
+
+impl<I, F, R> ParallelIterator for MapChunks<I, F>
+where
+    I: IndexedParallelIterator,
+    F: Fn(Vec<I::Item>) -> R + Send + Sync,
+    R: Send,
+{
+    type Item = R;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<R>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<I, F, R> IndexedParallelIterator for MapChunks<I, F>
+where
+    I: IndexedParallelIterator,
+    F: Fn(Vec<I::Item>) -> R + Send + Sync,
+    R: Send,
+{
+    fn len(&self) -> usize {
+        div_round_up(self.base.len(), self.chunk_size)
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        let len = self.base.len();
+        return self.base.with_producer(Callback {
+            chunk_size: self.chunk_size,
+            len,
+            map_op: self.map_op,
+            callback,
+        });
+
+        struct Callback<CB, F> {
+            chunk_size: usize,
+            len: usize,
+            map_op: F,
+            callback: CB,
+        }
+
+        impl<T, CB, F, R> ProducerCallback<T> for Callback<CB, F>
+        where
+            CB: ProducerCallback<R>,
+            F: Fn(Vec<T>) -> R + Send + Sync,
+        {
+            type Output = CB::Output;
+
+            fn callback<P>(self, base: P) -> CB::Output
+            where
+                P: Producer<Item = T>,
+            {
+                self.callback.callback(MapChunksProducer {
+                    chunk_size: self.chunk_size,
+                    len: self.len,
+                    map_op: &self.map_op,
+                    base,
+                })
+            }
+        }
+    }
+}
+
+struct MapChunksProducer<'f, P, F>
+where
+    P: Producer,
+{
+    chunk_size: usize,
+    len: usize,
+    map_op: &'f F,
+    base: P,
+}
+
+impl<'f, P, F, R> Producer for MapChunksProducer<'f, P, F>
+where
+    P: Producer,
+    F: Fn(Vec<P::Item>) -> R + Send + Sync,
+{
+    type Item = R;
+    type IntoIter = MapChunksSeq<'f, P, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MapChunksSeq {
+            chunk_size: self.chunk_size,
+            len: self.len,
+            map_op: self.map_op,
+            inner: if self.len > 0 { Some(self.base) } else { None },
+        }
+    }
+
+    fn min_len(&self) -> usize {
+        div_round_up(self.base.min_len(), self.chunk_size)
+    }
+
+    fn max_len(&self) -> usize {
+        self.base.max_len() / self.chunk_size
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let elem_index = min(index * self.chunk_size, self.len);
+        let (left, right) = self.base.split_at(elem_index);
+        (
+            MapChunksProducer {
+                chunk_size: self.chunk_size,
+                len: elem_index,
+                map_op: self.map_op,
+                base: left,
+            },
+            MapChunksProducer {
+                chunk_size: self.chunk_size,
+                len: self.len - elem_index,
+                map_op: self.map_op,
+                base: right,
+            },
+        )
+    }
+}
+
+struct MapChunksSeq<'f, P, F> {
+    chunk_size: usize,
+    len: usize,
+    map_op: &'f F,
+    inner: Option<P>,
+}
+
+impl<'f, P, F, R> Iterator for MapChunksSeq<'f, P, F>
+where
+    P: Producer,
+    F: Fn(Vec<P::Item>) -> R + Send + Sync,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let producer = self.inner.take()?;
+        if self.len > self.chunk_size {
+            let (left, right) = producer.split_at(self.chunk_size);
+            self.inner = Some(right);
+            self.len -= self.chunk_size;
+            Some((self.map_op)(left.into_iter().collect()))
+        } else {
+            debug_assert!(self.len > 0);
+            self.len = 0;
+            Some((self.map_op)(producer.into_iter().collect()))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'f, P, F, R> ExactSizeIterator for MapChunksSeq<'f, P, F>
+where
+    P: Producer,
+    F: Fn(Vec<P::Item>) -> R + Send + Sync,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        div_round_up(self.len, self.chunk_size)
+    }
+}
+
+impl<'f, P, F, R> DoubleEndedIterator for MapChunksSeq<'f, P, F>
+where
+    P: Producer,
+    F: Fn(Vec<P::Item>) -> R + Send + Sync,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let producer = self.inner.take()?;
+        if self.len > self.chunk_size {
+            let mut size = self.len % self.chunk_size;
+            if size == 0 {
+                size = self.chunk_size;
+            }
+            let (left, right) = producer.split_at(self.len - size);
+            self.inner = Some(left);
+            self.len -= size;
+            Some((self.map_op)(right.into_iter().collect()))
+        } else {
+            debug_assert!(self.len > 0);
+            self.len = 0;
+            Some((self.map_op)(producer.into_iter().collect()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_map_chunks() {
+        let words = "bishbashbosh!"
+            .chars()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map_chunks(4, |chunk| chunk.into_iter().collect::<String>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(words, vec!["bish", "bash", "bosh", "!"]);
+    }
+
+    #[test]
+    fn check_map_chunks_sorts_each_chunk() {
+        let sorted = vec![5, 3, 4, 1, 2, 0]
+            .into_par_iter()
+            .map_chunks(3, |mut chunk| {
+                chunk.sort_unstable();
+                chunk
+            })
+            .flatten_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(sorted, vec![3, 4, 5, 0, 1, 2]);
+    }
+
+    #[test]
+    fn check_map_chunks_len() {
+        assert_eq!(
+            4,
+            (0..8).into_par_iter().map_chunks(2, |c| c.len()).len()
+        );
+        assert_eq!(0, (0..0).into_par_iter().map_chunks(3, |c| c.len()).len());
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk_size must not be zero")]
+    fn check_map_chunks_zero_size() {
+        let _: Vec<usize> = vec![1, 2, 3]
+            .into_par_iter()
+            .map_chunks(0, |c| c.len())
+            .collect();
+    }
+}
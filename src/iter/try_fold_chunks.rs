@@ -0,0 +1,417 @@
+use std::cmp::min;
+use std::fmt::{self, Debug};
+use std::ops::ControlFlow;
+
+use super::plumbing::*;
+use super::*;
+use crate::math::div_round_up;
+
+/// A value that either completed a fold (`Output`) or short-circuited it with a residual
+/// (`Residual`), e.g. `Option<T>` or `Result<T, E>`.
+///
+/// This mirrors the `Try` abstraction that rayon's own `try_fold`/`try_for_each` adapters use
+/// internally, rather than the standard library's `std::ops::Try`, which remains unstable
+/// (`try_trait_v2`) and so cannot be named as a bound on stable Rust. It has to be `pub` since
+/// it's named as a bound on the public `try_fold_chunks()` method, but it's sealed via
+/// `private_decl!`/`private_impl!` (see `crate::private`) so that it can't be implemented outside
+/// of this crate, the same way rayon seals its own copy of this trait.
+pub trait Try {
+    private_decl! {}
+
+    type Output;
+    type Residual;
+
+    fn from_output(output: Self::Output) -> Self;
+    fn from_residual(residual: Self::Residual) -> Self;
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output>;
+}
+
+impl<U> Try for Option<U> {
+    private_impl! {}
+
+    type Output = U;
+    type Residual = ();
+
+    fn from_output(output: U) -> Self {
+        Some(output)
+    }
+
+    fn from_residual((): ()) -> Self {
+        None
+    }
+
+    fn branch(self) -> ControlFlow<(), U> {
+        match self {
+            Some(output) => ControlFlow::Continue(output),
+            None => ControlFlow::Break(()),
+        }
+    }
+}
+
+impl<U, E> Try for Result<U, E> {
+    private_impl! {}
+
+    type Output = U;
+    type Residual = E;
+
+    fn from_output(output: U) -> Self {
+        Ok(output)
+    }
+
+    fn from_residual(residual: E) -> Self {
+        Err(residual)
+    }
+
+    fn branch(self) -> ControlFlow<E, U> {
+        match self {
+            Ok(output) => ControlFlow::Continue(output),
+            Err(residual) => ControlFlow::Break(residual),
+        }
+    }
+}
+
+/// `TryFoldChunks` is an iterator that groups elements of an underlying iterator and applies a
+/// fallible function over them, producing a single value for each group, short-circuiting on the
+/// first chunk element for which the function returns a "failure" value.
+///
+/// This struct is created by the [`try_fold_chunks()`] method on [`IndexedParallelIterator`]
+///
+/// [`try_fold_chunks()`]: trait.IndexedParallelIterator.html#method.try_fold_chunks
+/// [`IndexedParallelIterator`]: trait.IndexedParallelIterator.html
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+#[derive(Clone)]
+pub struct TryFoldChunks<I, ID, F>
+where
+    I: IndexedParallelIterator,
+{
+    base: I,
+    chunk_size: usize,
+    fold_op: F,
+    identity: ID,
+}
+
+impl<I: IndexedParallelIterator + Debug, ID, F> Debug for TryFoldChunks<I, ID, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryFoldChunks")
+            .field("base", &self.base)
+            .field("chunk_size", &self.chunk_size)
+            .finish()
+    }
+}
+
+impl<I, ID, U, F, C> TryFoldChunks<I, ID, F>
+where
+    I: IndexedParallelIterator,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, I::Item) -> C + Send + Sync,
+    C: Try<Output = U>,
+    U: Send,
+{
+    /// Creates a new `TryFoldChunks` iterator
+    pub(super) fn new(base: I, chunk_size: usize, identity: ID, fold_op: F) -> Self {
+        TryFoldChunks {
+            base,
+            chunk_size,
+            identity,
+            fold_op,
+        }
+    }
+}
+
+impl<I, ID, U, F, C> ParallelIterator for TryFoldChunks<I, ID, F>
+where
+    I: IndexedParallelIterator,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, I::Item) -> C + Send + Sync,
+    C: Try<Output = U> + Send,
+    U: Send,
+{
+    type Item = C;
+
+    fn drive_unindexed<Con>(self, consumer: Con) -> Con::Result
+    where
+        Con: Consumer<C>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<I, ID, U, F, C> IndexedParallelIterator for TryFoldChunks<I, ID, F>
+where
+    I: IndexedParallelIterator,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, I::Item) -> C + Send + Sync,
+    C: Try<Output = U> + Send,
+    U: Send,
+{
+    fn len(&self) -> usize {
+        div_round_up(self.base.len(), self.chunk_size)
+    }
+
+    fn drive<Con>(self, consumer: Con) -> Con::Result
+    where
+        Con: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        let len = self.base.len();
+        return self.base.with_producer(Callback {
+            chunk_size: self.chunk_size,
+            len,
+            identity: self.identity,
+            fold_op: self.fold_op,
+            callback,
+        });
+
+        struct Callback<CB, ID, F> {
+            chunk_size: usize,
+            len: usize,
+            identity: ID,
+            fold_op: F,
+            callback: CB,
+        }
+
+        impl<T, CB, ID, U, F, C> ProducerCallback<T> for Callback<CB, ID, F>
+        where
+            CB: ProducerCallback<C>,
+            ID: Fn() -> U + Send + Sync,
+            F: Fn(U, T) -> C + Send + Sync,
+            C: Try<Output = U>,
+        {
+            type Output = CB::Output;
+
+            fn callback<P>(self, base: P) -> CB::Output
+            where
+                P: Producer<Item = T>,
+            {
+                self.callback.callback(TryFoldChunksProducer {
+                    chunk_size: self.chunk_size,
+                    len: self.len,
+                    identity: &self.identity,
+                    fold_op: &self.fold_op,
+                    base,
+                })
+            }
+        }
+    }
+}
+
+struct TryFoldChunksProducer<'f, P, ID, F>
+where
+    P: Producer,
+{
+    chunk_size: usize,
+    len: usize,
+    identity: &'f ID,
+    fold_op: &'f F,
+    base: P,
+}
+
+impl<'f, P, ID, U, F, C> Producer for TryFoldChunksProducer<'f, P, ID, F>
+where
+    P: Producer,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, P::Item) -> C + Send + Sync,
+    C: Try<Output = U>,
+{
+    type Item = C;
+    type IntoIter = TryFoldChunksSeq<'f, P, ID, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        TryFoldChunksSeq {
+            chunk_size: self.chunk_size,
+            len: self.len,
+            identity: self.identity,
+            fold_op: self.fold_op,
+            inner: if self.len > 0 { Some(self.base) } else { None },
+        }
+    }
+
+    fn min_len(&self) -> usize {
+        div_round_up(self.base.min_len(), self.chunk_size)
+    }
+
+    fn max_len(&self) -> usize {
+        self.base.max_len() / self.chunk_size
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let elem_index = min(index * self.chunk_size, self.len);
+        let (left, right) = self.base.split_at(elem_index);
+        (
+            TryFoldChunksProducer {
+                chunk_size: self.chunk_size,
+                len: elem_index,
+                identity: self.identity,
+                fold_op: self.fold_op,
+                base: left,
+            },
+            TryFoldChunksProducer {
+                chunk_size: self.chunk_size,
+                len: self.len - elem_index,
+                identity: self.identity,
+                fold_op: self.fold_op,
+                base: right,
+            },
+        )
+    }
+}
+
+struct TryFoldChunksSeq<'f, P, ID, F> {
+    chunk_size: usize,
+    len: usize,
+    identity: &'f ID,
+    fold_op: &'f F,
+    inner: Option<P>,
+}
+
+impl<'f, P, ID, U, F, C> TryFoldChunksSeq<'f, P, ID, F>
+where
+    P: Producer,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, P::Item) -> C + Send + Sync,
+    C: Try<Output = U>,
+{
+    /// Runs `fold_op` over every item yielded by `producer`, stopping as soon as it short-circuits.
+    fn fold_chunk(&self, producer: P) -> C {
+        let mut iter = producer.into_iter();
+        let mut acc = (self.identity)();
+        loop {
+            match iter.next() {
+                Some(item) => match (self.fold_op)(acc, item).branch() {
+                    ControlFlow::Continue(a) => acc = a,
+                    ControlFlow::Break(residual) => return C::from_residual(residual),
+                },
+                None => return C::from_output(acc),
+            }
+        }
+    }
+}
+
+impl<'f, P, ID, U, F, C> Iterator for TryFoldChunksSeq<'f, P, ID, F>
+where
+    P: Producer,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, P::Item) -> C + Send + Sync,
+    C: Try<Output = U>,
+{
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let producer = self.inner.take()?;
+        if self.len > self.chunk_size {
+            let (left, right) = producer.split_at(self.chunk_size);
+            self.inner = Some(right);
+            self.len -= self.chunk_size;
+            Some(self.fold_chunk(left))
+        } else {
+            debug_assert!(self.len > 0);
+            self.len = 0;
+            Some(self.fold_chunk(producer))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'f, P, ID, U, F, C> ExactSizeIterator for TryFoldChunksSeq<'f, P, ID, F>
+where
+    P: Producer,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, P::Item) -> C + Send + Sync,
+    C: Try<Output = U>,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        div_round_up(self.len, self.chunk_size)
+    }
+}
+
+impl<'f, P, ID, U, F, C> DoubleEndedIterator for TryFoldChunksSeq<'f, P, ID, F>
+where
+    P: Producer,
+    ID: Fn() -> U + Send + Sync,
+    F: Fn(U, P::Item) -> C + Send + Sync,
+    C: Try<Output = U>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let producer = self.inner.take()?;
+        if self.len > self.chunk_size {
+            let mut size = self.len % self.chunk_size;
+            if size == 0 {
+                size = self.chunk_size;
+            }
+            let (left, right) = producer.split_at(self.len - size);
+            self.inner = Some(left);
+            self.len -= size;
+            Some(self.fold_chunk(right))
+        } else {
+            debug_assert!(self.len > 0);
+            self.len = 0;
+            Some(self.fold_chunk(producer))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_try_fold_chunks() {
+        let result: Vec<Result<i32, &str>> = (0..10)
+            .into_par_iter()
+            .try_fold_chunks(3, || 0, |acc, x| if x == 7 { Err("boom") } else { Ok(acc + x) })
+            .collect();
+
+        assert_eq!(
+            result,
+            vec![Ok(0 + 1 + 2), Ok(3 + 4 + 5), Err("boom"), Ok(9)]
+        );
+    }
+
+    #[test]
+    fn check_try_fold_chunks_all_ok() {
+        let result: Vec<Option<i32>> = (0..9)
+            .into_par_iter()
+            .try_fold_chunks(3, || 0, |acc, x| Some(acc + x))
+            .collect();
+
+        assert_eq!(
+            result,
+            vec![Some(0 + 1 + 2), Some(3 + 4 + 5), Some(6 + 7 + 8)]
+        );
+    }
+
+    #[test]
+    fn check_try_fold_chunks_len() {
+        assert_eq!(
+            4,
+            (0..8)
+                .into_par_iter()
+                .try_fold_chunks(2, || 0, |acc, x| Ok::<i32, ()>(acc + x))
+                .len()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk_size must not be zero")]
+    fn check_try_fold_chunks_zero_size() {
+        let _: Vec<Option<i32>> = vec![1, 2, 3]
+            .into_par_iter()
+            .try_fold_chunks(0, || 0, |acc, x| Some(acc + x))
+            .collect();
+    }
+}